@@ -4,9 +4,43 @@ use bincode::error::{DecodeError, EncodeError};
 use bincode::{Decode, Encode};
 use cu29::prelude::*;
 use optimization_engine::constraints::Constraint;
-use optimization_engine::panoc::PANOCCache;
+use optimization_engine::panoc::{PANOCCache, PANOCOptimizer};
+use optimization_engine::{Optimizer, Problem};
 use serde::Serialize;
 use std::marker::PhantomData;
+use std::time::Duration;
+
+/// Finite-difference epsilon used to approximate the cost gradient handed to PANOC.
+const GRADIENT_EPSILON: f64 = 1e-6;
+
+/// Maximum number of PANOC iterations allowed per `next_control_output` call.
+const MAX_SOLVER_ITERATIONS: usize = 100;
+
+/// `CuDuration` arithmetic helpers that keep the sample-period gate and the rollout's
+/// integration step exact, instead of going through a lossy microseconds-as-`f32` detour.
+pub trait CuDurationExt {
+    /// Converts to seconds without truncating `CuDuration`'s full tick resolution.
+    fn as_secs_f64(&self) -> f64;
+    /// Integer-safe remainder against another duration. Returns `self` unchanged if `other`
+    /// is zero, since there's no meaningful period to stay phase-locked to.
+    fn rem_duration(&self, other: CuDuration) -> CuDuration;
+}
+
+impl CuDurationExt for CuDuration {
+    fn as_secs_f64(&self) -> f64 {
+        let CuDuration(ticks) = *self;
+        ticks as f64 / 1_000_000_000f64
+    }
+
+    fn rem_duration(&self, other: CuDuration) -> CuDuration {
+        let CuDuration(ticks) = *self;
+        let CuDuration(other_ticks) = other;
+        if other_ticks == 0 {
+            return *self;
+        }
+        CuDuration(ticks % other_ticks)
+    }
+}
 
 /// Output of the MPC controller.
 #[derive(Debug, Default, Clone, Encode, Decode, Serialize)]
@@ -15,13 +49,37 @@ pub struct MPCControlOutputPayload {
     pub output: Vec<f64>,
 }
 
+/// Constraint applied to the whole `horizon`-long control sequence: it re-applies the
+/// per-step constraint and the output limits to every `N`-wide block of the decision vector.
+struct HorizonConstraint<'a, const N: usize> {
+    per_step: &'a dyn Constraint,
+    output_limits: &'a [(f64, f64); N],
+}
+
+impl<const N: usize> Constraint for HorizonConstraint<'_, N> {
+    fn project(&self, x: &mut [f64]) {
+        for block in x.chunks_mut(N) {
+            self.per_step.project(block);
+            for (value, (min, max)) in block.iter_mut().zip(self.output_limits.iter()) {
+                *value = value.clamp(*min, *max);
+            }
+        }
+    }
+
+    fn is_convex(&self) -> bool {
+        self.per_step.is_convex()
+    }
+}
+
 /// This is the underlying standard MPC controller.
 pub struct MPCController<const N: usize> {
     setpoint: [f64; N],
     output_limits: [(f64, f64); N],
     sample_period: CuDuration,
+    // number of steps in the receding horizon
+    horizon: usize,
     // f(x, u) -> xdot
-    dynamics_function: Box<dyn Fn(&[f64], &[f64]) -> [f64] + Send>,
+    dynamics_function: Box<dyn Fn(&[f64], &[f64]) -> Vec<f64> + Send>,
 
     // MPC controller must have at least one of the below cost functions
     // optional state cost function, J(x, u) -> f64
@@ -30,7 +88,7 @@ pub struct MPCController<const N: usize> {
     // optional terminal cost function, J(x) -> f64
     terminal_cost: Option<Box<dyn Fn(&[f64]) -> f64 + Send>>,
 
-    constraint: Box<dyn Constraint>,
+    constraint: Box<dyn Constraint + Send>,
 
     // Internal state
     tolerance: f64,
@@ -38,6 +96,8 @@ pub struct MPCController<const N: usize> {
     last_error: [f64; N],
     elapsed: CuDuration,
     last_output: MPCControlOutputPayload,
+    // warm start for the next solve: the control sequence accepted on the last solve
+    last_solution: Vec<f64>,
 }
 
 impl<const N: usize> MPCController<N> {
@@ -46,16 +106,26 @@ impl<const N: usize> MPCController<N> {
         setpoint: [f64; N],
         output_limits: [(f64, f64); N],
         sample_period: CuDuration,
-        dynamics_function: impl Fn(&[f64], &[f64]) -> [f64] + Send + 'static,
+        horizon: usize,
+        dynamics_function: impl Fn(&[f64], &[f64]) -> Vec<f64> + Send + 'static,
         state_cost: Option<impl Fn(&[f64], &[f64]) -> f64 + Send + 'static>,
         terminal_cost: Option<impl Fn(&[f64]) -> f64 + Send + 'static>,
         constraint: impl Constraint + Send + 'static,
         tolerance: f64,
-    ) -> Self {
-        MPCController {
+    ) -> CuResult<Self> {
+        if horizon == 0 {
+            return Err(CuError::from("MPCController `horizon` must be at least 1"));
+        }
+        if state_cost.is_none() && terminal_cost.is_none() {
+            return Err(CuError::from(
+                "MPCController needs at least one of state_cost or terminal_cost",
+            ));
+        }
+        Ok(MPCController {
             setpoint,
             output_limits,
             sample_period,
+            horizon,
             dynamics_function: Box::new(dynamics_function),
             state_cost: state_cost.map(|state_cost_function| {
                 Box::new(state_cost_function) as Box<dyn Fn(&[f64], &[f64]) -> f64 + Send>
@@ -64,17 +134,19 @@ impl<const N: usize> MPCController<N> {
                 Box::new(terminal_cost_function) as Box<dyn Fn(&[f64]) -> f64 + Send>
             }),
             constraint: Box::new(constraint),
-            cache: PANOCCache::new(N, tolerance, 20),
+            cache: PANOCCache::new(N * horizon, tolerance, 20),
             elapsed: CuDuration::default(),
             last_output: MPCControlOutputPayload::default(),
+            last_solution: vec![0.0; N * horizon],
             last_error: [0.0; N],
-            tolerance: tolerance,
-        }
+            tolerance,
+        })
     }
 
     pub fn reset(&mut self) {
-        self.cache = PANOCCache::new(N, self.tolerance, 20);
+        self.cache = PANOCCache::new(N * self.horizon, self.tolerance, 20);
         self.last_error = [0.0; N];
+        self.last_solution = vec![0.0; N * self.horizon];
     }
 
     pub fn init_measurement(&mut self, measurement: &[f64; N]) {
@@ -92,26 +164,183 @@ impl<const N: usize> MPCController<N> {
         &mut self,
         measurement: &[f64; N],
         dt: CuDuration,
-    ) -> MPCControlOutputPayload {
+    ) -> CuResult<MPCControlOutputPayload> {
         self.elapsed += dt;
 
         if self.elapsed < self.sample_period {
             // if we update the MPC controller too fast, return its previous output
-            return self.last_output.clone();
+            return Ok(self.last_output.clone());
         }
 
         self.update_error(measurement);
-        let CuDuration(elapsed) = self.elapsed;
-        let dt = elapsed as f32 / 1_000_000f32;
+        let dt = self.elapsed.as_secs_f64();
 
-        // do MPC calculation
+        // do MPC calculation. Only the individual fields the rollout needs are captured here
+        // (rather than `self`) so the PANOC solve below can still borrow `self.cache` mutably.
+        let x0 = self.last_error;
+        let mut u = self.last_solution.clone();
+        let dynamics_function = &self.dynamics_function;
+        let state_cost = &self.state_cost;
+        let terminal_cost = &self.terminal_cost;
 
-        let output = MPCControlOutputPayload { output: vec![] };
+        let rollout_cost = |u: &[f64]| -> f64 {
+            let mut x = x0;
+            let mut cost = 0.0;
+            for uk in u.chunks(N) {
+                if let Some(state_cost) = state_cost {
+                    cost += state_cost(&x, uk);
+                }
+                let xdot = dynamics_function(&x, uk);
+                for i in 0..N {
+                    x[i] += xdot[i] * dt;
+                }
+            }
+            if let Some(terminal_cost) = terminal_cost {
+                cost += terminal_cost(&x);
+            }
+            cost
+        };
+        let cost_function = |u: &[f64], cost: &mut f64| -> Result<(), optimization_engine::SolverError> {
+            *cost = rollout_cost(u);
+            Ok(())
+        };
+        let gradient_function = |u: &[f64], grad: &mut [f64]| -> Result<(), optimization_engine::SolverError> {
+            let mut perturbed = u.to_vec();
+            for i in 0..u.len() {
+                let original = perturbed[i];
+                perturbed[i] = original + GRADIENT_EPSILON;
+                let cost_plus = rollout_cost(&perturbed);
+                perturbed[i] = original - GRADIENT_EPSILON;
+                let cost_minus = rollout_cost(&perturbed);
+                perturbed[i] = original;
+                grad[i] = (cost_plus - cost_minus) / (2.0 * GRADIENT_EPSILON);
+            }
+            Ok(())
+        };
+        let constraint = HorizonConstraint {
+            per_step: self.constraint.as_ref(),
+            output_limits: &self.output_limits,
+        };
+        let problem = Problem::new(&constraint, gradient_function, cost_function);
+        let mut optimizer = PANOCOptimizer::new(problem, &mut self.cache)
+            .with_max_iter(MAX_SOLVER_ITERATIONS);
+        optimizer
+            .solve(&mut u)
+            .map_err(|e| CuError::from(format!("PANOC solve failed: {e:?}")))?;
+
+        let mut output = u[..N].to_vec();
+        for (value, (min, max)) in output.iter_mut().zip(self.output_limits.iter()) {
+            *value = value.clamp(*min, *max);
+        }
 
+        // Warm-start the next solve by shifting the accepted sequence left by one step,
+        // repeating the last element so the next solve starts from a feasible guess.
+        let mut warm_start = u.clone();
+        if self.horizon > 1 {
+            warm_start.copy_within(N.., 0);
+            let last_block = u[(self.horizon - 1) * N..].to_vec();
+            warm_start[(self.horizon - 1) * N..].copy_from_slice(&last_block);
+        }
+        self.last_solution = warm_start;
+
+        let output = MPCControlOutputPayload { output };
         self.last_output = output.clone();
-        self.elapsed = CuDuration::default();
-        output
+        // Carry the overshoot forward instead of zeroing it, so the controller stays
+        // phase-locked to `sample_period` rather than drifting by the residual every tick.
+        self.elapsed = self.elapsed.rem_duration(self.sample_period);
+        Ok(output)
+    }
+}
+
+/// Simple per-dimension box constraint, used as the default feasible set derived from
+/// `output_limits` when a [`GenericMPCTask`] is built from a RON `ComponentConfig`.
+struct BoxConstraint {
+    limits: Vec<(f64, f64)>,
+}
+
+impl Constraint for BoxConstraint {
+    fn project(&self, x: &mut [f64]) {
+        for (value, (min, max)) in x.iter_mut().zip(self.limits.iter()) {
+            *value = value.clamp(*min, *max);
+        }
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
+
+/// Reads `sample_period` from the config, accepting either a raw microsecond integer or a
+/// duration string (e.g. `"10ms"`). Must be strictly positive: with a zero sample period the
+/// gate would never take its overshoot off `elapsed`, so the rollout step would keep growing.
+fn parse_sample_period(config: &ComponentConfig) -> CuResult<CuDuration> {
+    let sample_period = if let Some(microseconds) = config.get::<u64>("sample_period") {
+        CuDuration::from(Duration::from_micros(microseconds))
+    } else if let Some(raw) = config.get::<String>("sample_period") {
+        parse_duration_string(&raw)?
+    } else {
+        return Err(CuError::from(
+            "MPCTask config is missing `sample_period` (microseconds or duration string).",
+        ));
+    };
+    if sample_period == CuDuration::default() {
+        return Err(CuError::from("MPCTask `sample_period` must be positive."));
+    }
+    Ok(sample_period)
+}
+
+fn parse_duration_string(raw: &str) -> CuResult<CuDuration> {
+    let raw = raw.trim();
+    let split_at = raw
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(|| CuError::from(format!("Invalid duration string `{raw}`.")))?;
+    let (value, unit) = raw.split_at(split_at);
+    let value: f64 = value
+        .parse()
+        .map_err(|_| CuError::from(format!("Invalid duration string `{raw}`.")))?;
+    let duration = match unit {
+        "us" | "µs" => Duration::from_secs_f64(value / 1_000_000.0),
+        "ms" => Duration::from_secs_f64(value / 1_000.0),
+        "s" => Duration::from_secs_f64(value),
+        other => {
+            return Err(CuError::from(format!(
+                "Unknown duration unit `{other}` in `{raw}`."
+            )))
+        }
+    };
+    Ok(CuDuration::from(duration))
+}
+
+/// Reads a fixed-length array of `f64` from the config, erroring if the length doesn't match `N`.
+fn parse_array<const N: usize>(config: &ComponentConfig, key: &str) -> CuResult<[f64; N]> {
+    let values: Vec<f64> = config
+        .get::<Vec<f64>>(key)
+        .ok_or_else(|| CuError::from(format!("MPCTask config is missing `{key}`.")))?;
+    if values.len() != N {
+        return Err(CuError::from(format!(
+            "MPCTask `{key}` has {} entries, expected {N}.",
+            values.len()
+        )));
     }
+    values
+        .try_into()
+        .map_err(|_| CuError::from(format!("MPCTask `{key}` conversion failed.")))
+}
+
+/// Reads the `output_limits` list of `(min, max)` pairs from the config.
+fn parse_output_limits<const N: usize>(config: &ComponentConfig) -> CuResult<[(f64, f64); N]> {
+    let limits: Vec<(f64, f64)> = config.get::<Vec<(f64, f64)>>("output_limits").ok_or_else(|| {
+        CuError::from("MPCTask config is missing `output_limits`.".to_string())
+    })?;
+    if limits.len() != N {
+        return Err(CuError::from(format!(
+            "MPCTask `output_limits` has {} entries, expected {N}.",
+            limits.len()
+        )));
+    }
+    limits
+        .try_into()
+        .map_err(|_| CuError::from("MPCTask `output_limits` conversion failed."))
 }
 
 /// This is the Copper task encapsulating the MPC controller.
@@ -138,25 +367,80 @@ where
     where
         Self: Sized,
     {
-        match config {
-            Some(_config) => Err(CuError::from("WIP")),
-            None => Err(CuError::from("MPCTask needs a config.")),
+        let config = config.ok_or_else(|| CuError::from("MPCTask needs a config."))?;
+
+        let setpoint: [f64; N] = parse_array(config, "setpoint")?;
+        let output_limits: [(f64, f64); N] = parse_output_limits(config)?;
+        let sample_period = parse_sample_period(config)?;
+        let horizon = config
+            .get::<u32>("horizon")
+            .ok_or_else(|| CuError::from("MPCTask config is missing `horizon`."))?
+            as usize;
+        if horizon == 0 {
+            return Err(CuError::from("MPCTask `horizon` must be at least 1."));
         }
+        let tolerance = config.get::<f64>("tolerance").unwrap_or(1e-6);
+        let q: [f64; N] = parse_array(config, "q_weights")?;
+        let r: [f64; N] = parse_array(config, "r_weights")?;
+
+        // The task is generic over the measurement payload `I`, so it has no domain-specific
+        // model of the plant: the predicted state is simply the tracking error, driven directly
+        // by the control input (a generic single-integrator-per-axis assumption).
+        let dynamics_function = |_x: &[f64], u: &[f64]| -> Vec<f64> { u.to_vec() };
+        let state_cost = move |x: &[f64], u: &[f64]| -> f64 {
+            (0..N).map(|i| q[i] * x[i] * x[i] + r[i] * u[i] * u[i]).sum()
+        };
+        let terminal_cost = move |x: &[f64]| -> f64 { (0..N).map(|i| q[i] * x[i] * x[i]).sum() };
+        let constraint = BoxConstraint {
+            limits: output_limits.to_vec(),
+        };
+
+        let mpc = MPCController::new(
+            setpoint,
+            output_limits,
+            sample_period,
+            horizon,
+            dynamics_function,
+            Some(state_cost),
+            Some(terminal_cost),
+            constraint,
+            tolerance,
+        )?;
+
+        Ok(GenericMPCTask {
+            _marker: PhantomData,
+            mpc,
+            first_run: true,
+            last_tov: CuTime::default(),
+            setpoint: setpoint[0] as f32,
+        })
     }
 
     fn process(
         &mut self,
-        _clock: &RobotClock,
+        clock: &RobotClock,
         input: &Self::Input<'_>,
         output: &mut Self::Output<'_>,
     ) -> CuResult<()> {
-        match input.payload() {
-            Some(payload) => {
-                // WIP
-                output.clear_payload()
-            }
-            None => output.clear_payload(),
+        let Some(payload) = input.payload() else {
+            output.clear_payload();
+            return Ok(());
         };
+        let measurement: [f64; N] = payload.into();
+        let now = clock.now();
+
+        if self.first_run {
+            // Force the very first call to actually solve, and start `dt` at zero rather
+            // than whatever elapsed before this task started running.
+            self.mpc.init_measurement(&measurement);
+            self.first_run = false;
+            self.last_tov = now;
+        }
+        let dt = now - self.last_tov;
+        self.last_tov = now;
+
+        let control = self.mpc.next_control_output(&measurement, dt)?;
+        output.set_payload(control);
         Ok(())
     }
 
@@ -168,17 +452,315 @@ where
 }
 
 /// Store/Restore the internal state of the MPC controller.
+///
+/// `PANOCCache` itself isn't serializable, so instead of freezing the cache we freeze the
+/// PANOC warm-start sequence (`mpc.last_solution`) alongside the rest of the controller's
+/// state and rebuild a fresh cache on `thaw`; the restored sequence is the initial guess of
+/// the next solve. That's enough for
+/// the first post-restore `process` call to reproduce the trajectory it would have produced
+/// without the interruption.
 impl<I, const N: usize> Freezable for GenericMPCTask<I, N>
 where
     [f64; N]: for<'a> From<&'a I>,
 {
-    fn freeze<E: Encoder>(&self, _encoder: &mut E) -> Result<(), EncodeError> {
-        // WIP
+    fn freeze<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.mpc.last_error.encode(encoder)?;
+        self.mpc.elapsed.encode(encoder)?;
+        self.mpc.last_output.output.encode(encoder)?;
+        self.mpc.setpoint.encode(encoder)?;
+        self.mpc.last_solution.encode(encoder)?;
+        self.first_run.encode(encoder)?;
+        self.last_tov.encode(encoder)?;
+        self.setpoint.encode(encoder)?;
         Ok(())
     }
 
-    fn thaw<D: Decoder>(&mut self, _decoder: &mut D) -> Result<(), DecodeError> {
-        // WIP
+    fn thaw<D: Decoder>(&mut self, decoder: &mut D) -> Result<(), DecodeError> {
+        self.mpc.last_error = Decode::decode(decoder)?;
+        self.mpc.elapsed = Decode::decode(decoder)?;
+        self.mpc.last_output.output = Decode::decode(decoder)?;
+        self.mpc.setpoint = Decode::decode(decoder)?;
+        self.mpc.last_solution = Decode::decode(decoder)?;
+        self.first_run = Decode::decode(decoder)?;
+        self.last_tov = Decode::decode(decoder)?;
+        self.setpoint = Decode::decode(decoder)?;
+
+        // Rebuild the cache at the right dimension rather than trying to encode `PANOCCache`
+        // directly; the restored `last_solution` warm-starts the next solve.
+        self.mpc.cache = PANOCCache::new(N * self.mpc.horizon, self.mpc.tolerance, 20);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bincode::config;
+    use bincode::de::DecoderImpl;
+    use bincode::enc::EncoderImpl;
+
+    #[derive(Clone, Copy)]
+    struct DummyMeasurement([f64; 2]);
+
+    impl From<&DummyMeasurement> for [f64; 2] {
+        fn from(value: &DummyMeasurement) -> Self {
+            value.0
+        }
+    }
+
+    fn build_task() -> GenericMPCTask<DummyMeasurement, 2> {
+        let dynamics_function = |_x: &[f64], u: &[f64]| -> Vec<f64> { u.to_vec() };
+        let state_cost = |x: &[f64], u: &[f64]| -> f64 {
+            x.iter().map(|v| v * v).sum::<f64>() + u.iter().map(|v| v * v).sum::<f64>()
+        };
+        let terminal_cost = |x: &[f64]| -> f64 { x.iter().map(|v| v * v).sum() };
+        let constraint = BoxConstraint {
+            limits: vec![(-1.0, 1.0), (-1.0, 1.0)],
+        };
+        let mpc = MPCController::new(
+            [0.0, 0.0],
+            [(-1.0, 1.0), (-1.0, 1.0)],
+            CuDuration::from(Duration::from_millis(10)),
+            3,
+            dynamics_function,
+            Some(state_cost),
+            Some(terminal_cost),
+            constraint,
+            1e-4,
+        )
+        .expect("valid MPC configuration");
+
+        GenericMPCTask {
+            _marker: PhantomData,
+            mpc,
+            first_run: true,
+            last_tov: CuTime::default(),
+            setpoint: 0.0,
+        }
+    }
+
+    /// Builds the `ComponentConfig` RON used by the valid-config test, with each field
+    /// overridable so the validation-error tests can tweak exactly one key at a time.
+    fn component_config_ron(
+        setpoint: &str,
+        output_limits: &str,
+        sample_period: &str,
+        horizon: &str,
+        q_weights: &str,
+        r_weights: &str,
+    ) -> ComponentConfig {
+        let ron = format!(
+            "(setpoint: {setpoint}, output_limits: {output_limits}, \
+             sample_period: {sample_period}, horizon: {horizon}, \
+             q_weights: {q_weights}, r_weights: {r_weights})"
+        );
+        ron::from_str(&ron).expect("test RON should parse into a ComponentConfig")
+    }
+
+    fn valid_config() -> ComponentConfig {
+        component_config_ron(
+            "[0.0, 0.0]",
+            "[(-1.0, 1.0), (-1.0, 1.0)]",
+            "10000",
+            "3",
+            "[1.0, 1.0]",
+            "[0.1, 0.1]",
+        )
+    }
+
+    #[test]
+    fn next_control_output_tracks_setpoint_and_respects_output_limits() {
+        // A single-axis plant whose tracking-error dynamics are driven directly by the control
+        // (errordot = u). Minimizing the quadratic tracking cost should push the control toward
+        // closing the gap between measurement and setpoint.
+        let dynamics_function = |_x: &[f64], u: &[f64]| -> Vec<f64> { u.to_vec() };
+        let state_cost = |x: &[f64], u: &[f64]| -> f64 { x[0] * x[0] + 0.01 * u[0] * u[0] };
+        let terminal_cost = |x: &[f64]| -> f64 { x[0] * x[0] };
+        let output_limits = [(-0.2, 0.2)];
+        let constraint = BoxConstraint {
+            limits: output_limits.to_vec(),
+        };
+
+        let mut mpc = MPCController::new(
+            [1.0],
+            output_limits,
+            CuDuration::from(Duration::from_millis(10)),
+            5,
+            dynamics_function,
+            Some(state_cost),
+            Some(terminal_cost),
+            constraint,
+            1e-6,
+        )
+        .expect("valid MPC configuration");
+
+        let measurement = [0.0];
+        let step = CuDuration::from(Duration::from_millis(10));
+        let output = mpc
+            .next_control_output(&measurement, step)
+            .expect("solve should succeed");
+
+        assert_eq!(output.output.len(), 1);
+        let (min, max) = output_limits[0];
+        assert!(
+            output.output[0] >= min && output.output[0] <= max,
+            "control {} outside output_limits {:?}",
+            output.output[0],
+            output_limits[0]
+        );
+
+        // The setpoint is ahead of the measurement (tracking error starts at 1.0), so the
+        // optimal control closes the gap: a negative control on this error-driven plant.
+        assert!(
+            output.output[0] < 0.0,
+            "expected a negative, error-closing control, got {}",
+            output.output[0]
+        );
+
+        let predicted_next_error = 1.0 + output.output[0] * step.as_secs_f64();
+        assert!(
+            predicted_next_error.abs() < 1.0,
+            "expected the predicted tracking error to shrink, got {predicted_next_error}"
+        );
+    }
+
+    #[test]
+    fn new_from_config_succeeds_with_a_valid_config() {
+        let config = valid_config();
+        let task = GenericMPCTask::<DummyMeasurement, 2>::new(Some(&config));
+        assert!(task.is_ok(), "{:?}", task.err());
+    }
+
+    #[test]
+    fn new_from_config_rejects_missing_setpoint() {
+        let ron = "(output_limits: [(-1.0, 1.0), (-1.0, 1.0)], sample_period: 10000, \
+                    horizon: 3, q_weights: [1.0, 1.0], r_weights: [0.1, 0.1])";
+        let config: ComponentConfig = ron::from_str(ron).expect("valid RON");
+        let task = GenericMPCTask::<DummyMeasurement, 2>::new(Some(&config));
+        assert!(task.is_err());
+    }
+
+    #[test]
+    fn new_from_config_rejects_setpoint_length_mismatch() {
+        let config = component_config_ron(
+            "[0.0, 0.0, 0.0]",
+            "[(-1.0, 1.0), (-1.0, 1.0)]",
+            "10000",
+            "3",
+            "[1.0, 1.0]",
+            "[0.1, 0.1]",
+        );
+        let task = GenericMPCTask::<DummyMeasurement, 2>::new(Some(&config));
+        assert!(task.is_err());
+    }
+
+    #[test]
+    fn new_from_config_rejects_unknown_duration_unit() {
+        let config = component_config_ron(
+            "[0.0, 0.0]",
+            "[(-1.0, 1.0), (-1.0, 1.0)]",
+            "\"10fortnights\"",
+            "3",
+            "[1.0, 1.0]",
+            "[0.1, 0.1]",
+        );
+        let task = GenericMPCTask::<DummyMeasurement, 2>::new(Some(&config));
+        assert!(task.is_err());
+    }
+
+    #[test]
+    fn new_from_config_rejects_zero_horizon() {
+        let config = component_config_ron(
+            "[0.0, 0.0]",
+            "[(-1.0, 1.0), (-1.0, 1.0)]",
+            "10000",
+            "0",
+            "[1.0, 1.0]",
+            "[0.1, 0.1]",
+        );
+        let task = GenericMPCTask::<DummyMeasurement, 2>::new(Some(&config));
+        assert!(task.is_err());
+    }
+
+    #[test]
+    fn new_from_config_rejects_zero_sample_period() {
+        let config = component_config_ron(
+            "[0.0, 0.0]",
+            "[(-1.0, 1.0), (-1.0, 1.0)]",
+            "0",
+            "3",
+            "[1.0, 1.0]",
+            "[0.1, 0.1]",
+        );
+        let task = GenericMPCTask::<DummyMeasurement, 2>::new(Some(&config));
+        assert!(task.is_err());
+    }
+
+    #[test]
+    fn freeze_thaw_round_trip_preserves_warm_start() {
+        let step = CuDuration::from(Duration::from_millis(10));
+        let measurement: [f64; 2] = (&DummyMeasurement([0.2, -0.1])).into();
+
+        let mut uninterrupted = build_task();
+        let mut restarted = build_task();
+        for _ in 0..3 {
+            uninterrupted.mpc.next_control_output(&measurement, step).unwrap();
+            restarted.mpc.next_control_output(&measurement, step).unwrap();
+        }
+
+        // Freeze `restarted`, then thaw a brand-new task from the resulting bytes.
+        let mut buffer = Vec::new();
+        let mut encoder = EncoderImpl::new(&mut buffer, config::standard());
+        restarted.freeze(&mut encoder).unwrap();
+
+        let mut resumed = build_task();
+        let mut decoder = DecoderImpl::new(buffer.as_slice(), config::standard());
+        resumed.thaw(&mut decoder).unwrap();
+
+        // Both controllers keep running the same uninterrupted trajectory from here.
+        let expected = uninterrupted
+            .mpc
+            .next_control_output(&measurement, step)
+            .unwrap();
+        let actual = resumed.mpc.next_control_output(&measurement, step).unwrap();
+
+        for (e, a) in expected.output.iter().zip(actual.output.iter()) {
+            assert!((e - a).abs() < 1e-3, "expected {e}, got {a}");
+        }
+    }
+
+    #[test]
+    fn freeze_thaw_round_trip_preserves_nonzero_elapsed_residual() {
+        // 12ms doesn't evenly divide the 10ms sample period, so after a few steps `elapsed`
+        // should hold a nonzero overshoot carried forward by `rem_duration` rather than zero.
+        let step = CuDuration::from(Duration::from_millis(12));
+        let measurement: [f64; 2] = (&DummyMeasurement([0.2, -0.1])).into();
+
+        let mut uninterrupted = build_task();
+        let mut restarted = build_task();
+        for _ in 0..3 {
+            uninterrupted.mpc.next_control_output(&measurement, step).unwrap();
+            restarted.mpc.next_control_output(&measurement, step).unwrap();
+        }
+        assert_ne!(restarted.mpc.elapsed, CuDuration::default());
+
+        let mut buffer = Vec::new();
+        let mut encoder = EncoderImpl::new(&mut buffer, config::standard());
+        restarted.freeze(&mut encoder).unwrap();
+
+        let mut resumed = build_task();
+        let mut decoder = DecoderImpl::new(buffer.as_slice(), config::standard());
+        resumed.thaw(&mut decoder).unwrap();
+        assert_eq!(resumed.mpc.elapsed, restarted.mpc.elapsed);
+
+        let expected = uninterrupted
+            .mpc
+            .next_control_output(&measurement, step)
+            .unwrap();
+        let actual = resumed.mpc.next_control_output(&measurement, step).unwrap();
+
+        for (e, a) in expected.output.iter().zip(actual.output.iter()) {
+            assert!((e - a).abs() < 1e-3, "expected {e}, got {a}");
+        }
+    }
+}
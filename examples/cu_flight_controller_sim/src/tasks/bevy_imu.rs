@@ -1,26 +1,90 @@
-pub use cu_sensor_payloads::ImuPayload;
+use cu29::prelude::*;
 use cu29::{
     cutask::{CuSrcTask, Freezable},
     output_msg,
 };
+pub use cu_sensor_payloads::ImuPayload;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use std::sync::{Arc, RwLock};
+
+/// Minimal rigid-body state sampled out of the Bevy simulation each tick: orientation as a
+/// body-to-world unit quaternion `[x, y, z, w]`, angular velocity in the body frame (rad/s),
+/// and linear acceleration in the body frame (m/s^2, excluding gravity).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BevyBodyState {
+    pub orientation: [f32; 4],
+    pub angular_velocity: [f32; 3],
+    pub linear_acceleration: [f32; 3],
+}
+
+/// Handle the Bevy side updates every simulation step; `BevyIMU` reads it on every `process`.
+pub type SharedBevyBodyState = Arc<RwLock<BevyBodyState>>;
 
-pub struct BevyIMU {}
+/// A constant per-axis bias plus a Gaussian random walk, used to model slowly-drifting bias.
+#[derive(Debug, Clone, Copy, Default)]
+struct AxisBias {
+    value: [f32; 3],
+    random_walk_std: [f32; 3],
+}
+
+impl AxisBias {
+    fn step(&mut self, rng: &mut StdRng) {
+        for i in 0..3 {
+            self.value[i] += sample_noise(rng, self.random_walk_std[i]);
+        }
+    }
+}
+
+/// Synthetic IMU source task for the flight-sim example: pulls the simulated body state out
+/// of Bevy and turns it into a timestamped, noisy `ImuPayload`, so downstream stages (including
+/// the MPC stage) see realistic measurements instead of the simulator's ground truth.
+pub struct BevyIMU {
+    body_state: SharedBevyBodyState,
+    // gravity in the world frame, projected into the body frame every tick
+    gravity: [f32; 3],
+    gyro_bias: AxisBias,
+    accel_bias: AxisBias,
+    gyro_noise_std: [f32; 3],
+    accel_noise_std: [f32; 3],
+    rng: StdRng,
+}
 
 impl Freezable for BevyIMU {}
 
 impl CuSrcTask for BevyIMU {
     type Output<'m> = output_msg!(ImuPayload);
 
-    type Resources<'r> = ();
+    type Resources<'r> = SharedBevyBodyState;
 
     fn new(
-        _config: Option<&cu29::prelude::ComponentConfig>,
-        _resources: Self::Resources<'_>,
+        config: Option<&cu29::prelude::ComponentConfig>,
+        resources: Self::Resources<'_>,
     ) -> cu29::CuResult<Self>
     where
         Self: Sized,
     {
-        todo!()
+        let config = config.ok_or_else(|| CuError::from("BevyIMU needs a config."))?;
+
+        let gravity_magnitude = config.get::<f64>("gravity").unwrap_or(9.81) as f32;
+        let seed = config.get::<u64>("rng_seed").unwrap_or(0);
+
+        Ok(BevyIMU {
+            body_state: resources,
+            gravity: [0.0, 0.0, gravity_magnitude],
+            gyro_bias: AxisBias {
+                value: parse_axis(config, "gyro_bias")?,
+                random_walk_std: parse_axis(config, "gyro_bias_random_walk_std")?,
+            },
+            accel_bias: AxisBias {
+                value: parse_axis(config, "accel_bias")?,
+                random_walk_std: parse_axis(config, "accel_bias_random_walk_std")?,
+            },
+            gyro_noise_std: parse_axis(config, "gyro_noise_std")?,
+            accel_noise_std: parse_axis(config, "accel_noise_std")?,
+            rng: StdRng::seed_from_u64(seed),
+        })
     }
 
     fn process<'o>(
@@ -28,6 +92,121 @@ impl CuSrcTask for BevyIMU {
         clock: &cu29::prelude::RobotClock,
         new_msg: &mut Self::Output<'o>,
     ) -> cu29::CuResult<()> {
-        todo!()
+        let state = *self
+            .body_state
+            .read()
+            .map_err(|_| CuError::from("BevyIMU body state lock poisoned."))?;
+
+        // Let the bias drift a little every tick, then sample the measurement around it.
+        self.gyro_bias.step(&mut self.rng);
+        self.accel_bias.step(&mut self.rng);
+
+        let gravity_body = rotate_by_conjugate(state.orientation, self.gravity);
+
+        let mut gyro = [0.0f32; 3];
+        let mut accel = [0.0f32; 3];
+        for i in 0..3 {
+            gyro[i] = state.angular_velocity[i]
+                + self.gyro_bias.value[i]
+                + sample_noise(&mut self.rng, self.gyro_noise_std[i]);
+            // an accelerometer at rest measures the reaction to gravity, not gravity itself
+            accel[i] = state.linear_acceleration[i] - gravity_body[i]
+                + self.accel_bias.value[i]
+                + sample_noise(&mut self.rng, self.accel_noise_std[i]);
+        }
+
+        new_msg.set_payload(ImuPayload { gyro, accel });
+        new_msg.tov = clock.now().into();
+        Ok(())
+    }
+}
+
+/// Reads a 3-entry `[f32; 3]` array from the config, defaulting to zero when absent.
+fn parse_axis(config: &cu29::prelude::ComponentConfig, key: &str) -> cu29::CuResult<[f32; 3]> {
+    let values: Vec<f32> = config.get::<Vec<f32>>(key).unwrap_or_else(|| vec![0.0; 3]);
+    values
+        .try_into()
+        .map_err(|_| CuError::from(format!("BevyIMU `{key}` must have exactly 3 entries.")))
+}
+
+fn sample_noise(rng: &mut StdRng, std: f32) -> f32 {
+    if std <= 0.0 {
+        return 0.0;
+    }
+    Normal::new(0.0, std as f64)
+        .expect("invalid noise standard deviation")
+        .sample(rng) as f32
+}
+
+/// Rotates `v` (a world-frame vector) into the body frame by the conjugate of `orientation`
+/// (a body-to-world unit quaternion `[x, y, z, w]`).
+fn rotate_by_conjugate(orientation: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    let [x, y, z, w] = orientation;
+    quat_rotate([-x, -y, -z, w], v)
+}
+
+fn quat_rotate(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    let [x, y, z, w] = q;
+    let u = [x, y, z];
+    let uv = cross(u, v);
+    let uuv = cross(u, uv);
+    [
+        v[0] + 2.0 * (w * uv[0] + uuv[0]),
+        v[1] + 2.0 * (w * uv[1] + uuv[1]),
+        v[2] + 2.0 * (w * uv[2] + uuv[2]),
+    ]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-5, "expected {expected:?}, got {actual:?}");
+        }
+    }
+
+    #[test]
+    fn quat_rotate_identity_is_a_no_op() {
+        let v = [1.0, -2.0, 3.5];
+        assert_close(quat_rotate([0.0, 0.0, 0.0, 1.0], v), v);
+    }
+
+    #[test]
+    fn quat_rotate_90_degrees_about_z_maps_x_to_y() {
+        let half_angle: f32 = std::f32::consts::FRAC_PI_4;
+        let q = [0.0, 0.0, half_angle.sin(), half_angle.cos()];
+        assert_close(quat_rotate(q, [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn rotate_by_conjugate_inverts_quat_rotate() {
+        let half_angle: f32 = std::f32::consts::FRAC_PI_6;
+        let q = [half_angle.sin(), 0.0, 0.0, half_angle.cos()];
+        let v = [0.3, -0.7, 1.2];
+        let rotated = quat_rotate(q, v);
+        assert_close(rotate_by_conjugate(q, rotated), v);
+    }
+
+    #[test]
+    fn sample_noise_with_zero_std_is_exactly_zero() {
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(sample_noise(&mut rng, 0.0), 0.0);
+    }
+
+    #[test]
+    fn sample_noise_with_positive_std_is_reproducible_for_a_given_seed() {
+        let mut rng_a = StdRng::seed_from_u64(7);
+        let mut rng_b = StdRng::seed_from_u64(7);
+        assert_eq!(sample_noise(&mut rng_a, 1.0), sample_noise(&mut rng_b, 1.0));
     }
 }